@@ -0,0 +1,159 @@
+use crate::dom::Node;
+
+/// Number of counters in the filter; indices are derived from 12 bits of a
+/// descriptor's hash, so this must stay a power of two >= 4096.
+const NUM_COUNTERS: usize = 4096;
+const SLOT_MASK: u32 = (NUM_COUNTERS - 1) as u32;
+
+/// A counting bloom filter over an element's ancestor-chain descriptors
+/// (lowercased tag name, id, classes), used to cheaply reject descendant
+/// selectors whose target ancestor provably isn't on the current path.
+///
+/// Counters (rather than bits) let the filter be decremented as the style
+/// tree walk backtracks up the tree, so it always reflects exactly the
+/// ancestors currently on the path.
+pub struct BloomFilter {
+    counters: [u8; NUM_COUNTERS],
+}
+
+impl BloomFilter {
+    pub fn new() -> Self {
+        BloomFilter {
+            counters: [0; NUM_COUNTERS],
+        }
+    }
+
+    /// Add a node's descriptors to the filter; call when descending into it
+    pub fn insert_node(&mut self, node: &Node) {
+        for hash in node_descriptor_hashes(node) {
+            for slot in slots(hash) {
+                self.counters[slot] = self.counters[slot].saturating_add(1);
+            }
+        }
+    }
+
+    /// Remove a node's descriptors from the filter; call when backtracking
+    /// out of it, undoing a prior `insert_node`
+    pub fn remove_node(&mut self, node: &Node) {
+        for hash in node_descriptor_hashes(node) {
+            for slot in slots(hash) {
+                if self.counters[slot] > 0 {
+                    self.counters[slot] -= 1;
+                }
+            }
+        }
+    }
+
+    /// `false` means the descriptor is definitely not on the current path;
+    /// `true` means it might be (subject to the usual bloom filter false
+    /// positives).
+    pub fn might_contain(&self, descriptor: &str) -> bool {
+        slots(hash_descriptor(descriptor))
+            .into_iter()
+            .all(|slot| self.counters[slot] > 0)
+    }
+}
+
+impl Default for BloomFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Two 12-bit slot indices derived from a 32-bit hash
+fn slots(hash: u32) -> [usize; 2] {
+    [(hash & SLOT_MASK) as usize, ((hash >> 12) & SLOT_MASK) as usize]
+}
+
+/// FNV-1a; any reasonably-distributed hash works here since the filter only
+/// needs to cheaply reject, not uniquely identify, descriptors
+fn hash_descriptor(s: &str) -> u32 {
+    let mut hash: u32 = 0x811c9dc5;
+    for byte in s.as_bytes() {
+        hash ^= u32::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+fn node_descriptor_hashes(node: &Node) -> Vec<u32> {
+    let mut hashes = Vec::new();
+    if let Some(tag) = node.get_tag() {
+        hashes.push(hash_descriptor(&tag.to_lowercase()));
+    }
+    if let Some(id) = node.id() {
+        hashes.push(hash_descriptor(id));
+    }
+    for class in node.classes() {
+        hashes.push(hash_descriptor(class));
+    }
+    hashes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::AttrMap;
+
+    fn element(tag: &str, id: Option<&str>, class: Option<&str>) -> Node {
+        let mut attrs = AttrMap::new();
+        if let Some(id) = id {
+            attrs.insert("id".to_string(), id.to_string());
+        }
+        if let Some(class) = class {
+            attrs.insert("class".to_string(), class.to_string());
+        }
+        Node::new_element(tag.to_string(), attrs, Vec::new())
+    }
+
+    #[test]
+    fn test_absent_descriptor_is_rejected() {
+        let filter = BloomFilter::new();
+        assert!(!filter.might_contain("div"));
+    }
+
+    #[test]
+    fn test_inserted_descriptors_are_found() {
+        let mut filter = BloomFilter::new();
+        let node = element("div", Some("main"), Some("box"));
+        filter.insert_node(&node);
+
+        assert!(filter.might_contain("div"));
+        assert!(filter.might_contain("main"));
+        assert!(filter.might_contain("box"));
+        assert!(!filter.might_contain("span"));
+    }
+
+    #[test]
+    fn test_tag_lookup_is_case_insensitive() {
+        let mut filter = BloomFilter::new();
+        filter.insert_node(&element("DIV", None, None));
+
+        assert!(filter.might_contain("div"));
+    }
+
+    #[test]
+    fn test_remove_node_reverses_insert() {
+        let mut filter = BloomFilter::new();
+        let node = element("div", Some("main"), None);
+        filter.insert_node(&node);
+        filter.remove_node(&node);
+
+        assert!(!filter.might_contain("div"));
+        assert!(!filter.might_contain("main"));
+    }
+
+    #[test]
+    fn test_shared_descriptor_survives_sibling_removal() {
+        let mut filter = BloomFilter::new();
+        let a = element("p", None, Some("shared"));
+        let b = element("p", None, Some("shared"));
+        filter.insert_node(&a);
+        filter.insert_node(&b);
+        filter.remove_node(&a);
+
+        // b's "p"/"shared" counters are still > 0
+        assert!(filter.might_contain("p"));
+        assert!(filter.might_contain("shared"));
+    }
+}
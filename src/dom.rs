@@ -1,4 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+use crate::parser::css::Css;
+use crate::style;
 
 #[derive(Debug)]
 pub struct Node {
@@ -52,6 +55,43 @@ impl Node {
             _ => None,
         }
     }
+
+    pub fn is_element(&self) -> bool {
+        matches!(self.node_type, NodeType::Element(_))
+    }
+
+    /// Look up an attribute's value; `None` for text nodes or missing attributes
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        match &self.node_type {
+            NodeType::Element(elem) => elem.attributes.get(name).map(|s| s.as_str()),
+            NodeType::Text(_) => None,
+        }
+    }
+
+    pub fn id(&self) -> Option<&str> {
+        self.attr("id")
+    }
+
+    pub fn classes(&self) -> HashSet<&str> {
+        match self.attr("class") {
+            Some(classes) => classes.split_whitespace().collect(),
+            None => HashSet::new(),
+        }
+    }
+
+    /// The first descendant (document order, self excluded) matching a css
+    /// selector, which may itself be a comma-separated list of selectors
+    pub fn select(&self, selector: &str) -> Option<&Node> {
+        let selectors = Css::parse_one(selector.to_string());
+        style::select(self, &selectors)
+    }
+
+    /// Every descendant (document order, self excluded) matching a css
+    /// selector, which may itself be a comma-separated list of selectors
+    pub fn select_all(&self, selector: &str) -> Vec<&Node> {
+        let selectors = Css::parse_one(selector.to_string());
+        style::select_all(self, &selectors)
+    }
 }
 
 #[derive(Debug)]
@@ -68,6 +108,7 @@ struct ElementData {
 
 pub type AttrMap = HashMap<String, String>;
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -134,4 +175,53 @@ mod tests {
             _ => panic!("Expected text node, found unexpected node type"),
         }
     }
+
+    #[test]
+    fn test_select_finds_first_matching_descendant() {
+        let mut attrs = AttrMap::new();
+        attrs.insert("class".to_string(), "item".to_string());
+        let child1 = Node::new_element("li".to_string(), attrs, Vec::new());
+        let child2 = Node::new_element("li".to_string(), AttrMap::new(), Vec::new());
+        let root = Node::new_element("ul".to_string(), AttrMap::new(), vec![child1, child2]);
+
+        let found = root.select(".item");
+
+        assert!(found.unwrap().get_tag().as_deref() == Some("li"));
+    }
+
+    #[test]
+    fn test_select_all_finds_every_matching_descendant() {
+        let child1 = Node::new_element("li".to_string(), AttrMap::new(), Vec::new());
+        let child2 = Node::new_element("li".to_string(), AttrMap::new(), Vec::new());
+        let root = Node::new_element("ul".to_string(), AttrMap::new(), vec![child1, child2]);
+
+        assert!(root.select_all("li").len() == 2);
+    }
+
+    #[test]
+    fn test_select_returns_none_when_nothing_matches() {
+        let root = Node::new_element("div".to_string(), AttrMap::new(), Vec::new());
+
+        assert!(root.select("span").is_none());
+    }
+
+    #[test]
+    fn test_select_excludes_the_node_itself() {
+        let mut attrs = AttrMap::new();
+        attrs.insert("class".to_string(), "item".to_string());
+        let child = Node::new_element("li".to_string(), attrs.clone(), Vec::new());
+        let root = Node::new_element("ul".to_string(), attrs, vec![child]);
+
+        assert!(root.select(".item").unwrap().get_tag().as_deref() == Some("li"));
+        assert!(root.select_all(".item").len() == 1);
+    }
+
+    #[test]
+    fn test_select_all_matches_every_selector_in_a_comma_separated_list() {
+        let li = Node::new_element("li".to_string(), AttrMap::new(), Vec::new());
+        let p = Node::new_element("p".to_string(), AttrMap::new(), Vec::new());
+        let root = Node::new_element("div".to_string(), AttrMap::new(), vec![li, p]);
+
+        assert!(root.select_all("li, p").len() == 2);
+    }
 }
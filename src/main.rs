@@ -1,12 +1,26 @@
+mod bloom;
 mod dom;
+mod nth;
 mod parser;
+mod style;
 
+use parser::css::Css;
 use parser::html::Html;
 use std::fs;
+use style::style_tree;
 
 fn main() {
     let document = fs::read_to_string("test.html").unwrap();
-
     let dom_root = Html::parse(document);
     println!("{:#?}", dom_root);
+
+    let stylesheet_source = fs::read_to_string("test.css").unwrap();
+    let stylesheet = Css::parse(stylesheet_source);
+    println!("{:#?}", stylesheet);
+
+    let styled_root = style_tree(&dom_root, &stylesheet);
+    println!("{:#?}", styled_root);
+
+    println!("{:#?}", dom_root.select("p"));
+    println!("{:#?}", dom_root.select_all("p"));
 }
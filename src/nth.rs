@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use crate::dom::Node;
+
+/// Per-parent memoization of each child's 1-based position among its element
+/// siblings (skipping text nodes), plus the total element sibling count.
+/// Computed once per distinct sibling list and reused for every child of
+/// that parent, since `:nth-child`/`:first-child`/`:last-child` matching
+/// would otherwise recount siblings from scratch for every element.
+pub struct NthIndexCache {
+    entries: HashMap<usize, NthEntry>,
+}
+
+struct NthEntry {
+    /// 1-based element position by sibling index; `None` for non-element siblings
+    element_positions: Vec<Option<usize>>,
+    element_count: usize,
+}
+
+impl NthIndexCache {
+    pub fn new() -> Self {
+        NthIndexCache {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// The 1-based position of the sibling at `index` among element
+    /// siblings, and the total element sibling count; `None` if that sibling
+    /// isn't an element.
+    pub fn position(&mut self, siblings: &[Node], index: usize) -> Option<(usize, usize)> {
+        let entry = self.entry_for(siblings);
+        entry.element_positions[index].map(|position| (position, entry.element_count))
+    }
+
+    fn entry_for(&mut self, siblings: &[Node]) -> &NthEntry {
+        // the sibling slice's address uniquely and stably identifies its
+        // parent for the lifetime of a single style_tree walk
+        let key = siblings.as_ptr() as usize;
+        self.entries.entry(key).or_insert_with(|| {
+            let mut element_count = 0;
+            let element_positions = siblings
+                .iter()
+                .map(|node| {
+                    if node.is_element() {
+                        element_count += 1;
+                        Some(element_count)
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            NthEntry {
+                element_positions,
+                element_count,
+            }
+        })
+    }
+}
+
+impl Default for NthIndexCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dom::AttrMap;
+
+    fn element(tag: &str) -> Node {
+        Node::new_element(tag.to_string(), AttrMap::new(), Vec::new())
+    }
+
+    #[test]
+    fn test_position_counts_only_elements() {
+        let siblings = vec![
+            Node::new_text("hi".to_string()),
+            element("p"),
+            element("p"),
+        ];
+        let mut cache = NthIndexCache::new();
+
+        assert_eq!(cache.position(&siblings, 0), None);
+        assert_eq!(cache.position(&siblings, 1), Some((1, 2)));
+        assert_eq!(cache.position(&siblings, 2), Some((2, 2)));
+    }
+
+    #[test]
+    fn test_position_is_cached_across_calls() {
+        let siblings = vec![element("p"), element("p")];
+        let mut cache = NthIndexCache::new();
+
+        assert_eq!(cache.position(&siblings, 0), Some((1, 2)));
+        // same sibling list queried again should hit the memoized entry
+        assert_eq!(cache.position(&siblings, 1), Some((2, 2)));
+        assert_eq!(cache.entries.len(), 1);
+    }
+}
@@ -1,21 +1,40 @@
 use std::usize;
 
-struct Stylesheet {
-    rules: Vec<Rule>,
+#[derive(Debug)]
+pub struct Stylesheet {
+    pub rules: Vec<Rule>,
 }
 
-struct Rule {
+#[derive(Debug)]
+pub struct Rule {
     /// Currently only supports simple selectors
-    selectors: Vec<Selector>,
-    declarations: Vec<Declaration>,
+    pub selectors: Vec<Selector>,
+    pub declarations: Vec<Declaration>,
 }
 
 #[derive(Debug)]
 /// css seletor used to select dom nodes to apply styles to.
-enum Selector {
+pub enum Selector {
     /// Can be a tag name, id prefixed with # or class prefixed with .
     /// `*` acts as universal selector
     Simple(SimpleSelector),
+    /// A sequence of compound selectors in source (left-to-right) order, each
+    /// paired with the combinator describing its relationship to the part on
+    /// its left. The leftmost part's combinator is never consulted.
+    Complex(Vec<(SimpleSelector, Combinator)>),
+}
+
+/// Relationship between a compound selector and the one to its left
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combinator {
+    /// ` ` - matches any ancestor
+    Descendant,
+    /// `>` - matches the direct parent only
+    Child,
+    /// `+` - matches the immediately preceding sibling only
+    Adjacent,
+    /// `~` - matches any earlier sibling
+    Sibling,
 }
 
 /// Used to decide which style applies in a conflict
@@ -23,19 +42,32 @@ pub type Specificity = (usize, usize, usize);
 
 impl Selector {
     pub fn specificity(&self) -> Specificity {
-        let Selector::Simple(ref simple) = *self;
-        let a = simple.id.iter().count();
-        let b = simple.class.len();
-        let c = simple.tag_name.iter().count();
-        (a, b, c)
+        match self {
+            Selector::Simple(simple) => simple_specificity(simple),
+            Selector::Complex(parts) => {
+                parts.iter().fold((0, 0, 0), |(a, b, c), (simple, _)| {
+                    let (sa, sb, sc) = simple_specificity(simple);
+                    (a + sa, b + sb, c + sc)
+                })
+            }
+        }
     }
 }
 
+fn simple_specificity(selector: &SimpleSelector) -> Specificity {
+    let a = selector.id.iter().count();
+    let b = selector.class.len() + selector.attributes.len() + selector.pseudo_classes.len();
+    let c = selector.tag_name.iter().count();
+    (a, b, c)
+}
+
 #[derive(Debug)]
-struct SimpleSelector {
-    tag_name: Option<String>,
-    id: Option<String>,
-    class: Vec<String>,
+pub struct SimpleSelector {
+    pub tag_name: Option<String>,
+    pub id: Option<String>,
+    pub class: Vec<String>,
+    pub attributes: Vec<AttrSelector>,
+    pub pseudo_classes: Vec<PseudoClass>,
 }
 
 impl SimpleSelector {
@@ -44,38 +76,78 @@ impl SimpleSelector {
             tag_name: None,
             id: None,
             class: Vec::new(),
+            attributes: Vec::new(),
+            pseudo_classes: Vec::new(),
         }
     }
 }
 
+/// A `:first-child`, `:last-child` or `:nth-child(an+b)` selector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PseudoClass {
+    First,
+    Last,
+    /// The `(a, b)` coefficients of the `an+b` microsyntax
+    Nth(i32, i32),
+}
+
+/// An `[attr]`, `[attr=value]`, `[attr^=value]`, ... selector
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttrSelector {
+    pub name: String,
+    /// `None` for a bare `[attr]` existence check
+    pub operator: Option<AttrOperator>,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttrOperator {
+    /// `=` - exact match
+    Equals,
+    /// `~=` - value is a whitespace-separated word in the attribute
+    Includes,
+    /// `^=` - attribute starts with value
+    Prefix,
+    /// `$=` - attribute ends with value
+    Suffix,
+    /// `*=` - attribute contains value
+    Substring,
+    /// `|=` - attribute equals value, or starts with `value-`
+    DashMatch,
+}
+
+#[derive(Debug)]
 /// A key value pair separated by a `:`
 /// used to specify css properties
-struct Declaration {
-    name: String,
-    value: Value,
+pub struct Declaration {
+    pub name: String,
+    pub value: Value,
 }
 
 /// For simplicity, only support a small subset of css values
-enum Value {
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
     Keyword(String),
     Length(f32, Unit),
     ColorValue(Color),
 }
 
 /// Units for css properties
-enum Unit {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unit {
     Px,
 }
 
 /// Color using rgba values
-struct Color {
-    r: u8,
-    g: u8,
-    b: u8,
-    a: u8,
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
 }
 
-struct Css {
+pub struct Css {
     pos: usize,
     input: String,
 }
@@ -93,6 +165,11 @@ impl Css {
         self.pos >= self.input.len()
     }
 
+    /// Read the current character without consuming it, or `None` at eof
+    fn peek_char(&self) -> Option<char> {
+        self.input.get(self.pos..).and_then(|s| s.chars().next())
+    }
+
     fn consume_char(&mut self) -> char {
         let mut iter = self.input.get(self.pos..).unwrap().char_indices();
         let (_, curr) = iter.next().unwrap();
@@ -132,6 +209,14 @@ impl Css {
                     // universal selector
                     self.consume_char();
                 }
+                '[' => {
+                    self.consume_char();
+                    selector.attributes.push(self.parse_attr_selector());
+                }
+                ':' => {
+                    self.consume_char();
+                    selector.pseudo_classes.push(self.parse_pseudo_class());
+                }
                 c if valid_identifier_char(c) => {
                     selector.tag_name = Some(self.parse_identifier());
                 }
@@ -141,22 +226,182 @@ impl Css {
         selector
     }
 
+    /// Parse the inside of an `[attr]` / `[attr<op>value]` selector; the
+    /// opening `[` has already been consumed
+    fn parse_attr_selector(&mut self) -> AttrSelector {
+        self.consume_whitespace();
+        let name = self.parse_identifier();
+        self.consume_whitespace();
+
+        let operator = match self.peek_char() {
+            Some(']') => None,
+            Some('=') => {
+                self.consume_char();
+                Some(AttrOperator::Equals)
+            }
+            Some('~') => {
+                self.consume_char();
+                assert!(self.consume_char() == '=');
+                Some(AttrOperator::Includes)
+            }
+            Some('^') => {
+                self.consume_char();
+                assert!(self.consume_char() == '=');
+                Some(AttrOperator::Prefix)
+            }
+            Some('$') => {
+                self.consume_char();
+                assert!(self.consume_char() == '=');
+                Some(AttrOperator::Suffix)
+            }
+            Some('*') => {
+                self.consume_char();
+                assert!(self.consume_char() == '=');
+                Some(AttrOperator::Substring)
+            }
+            Some('|') => {
+                self.consume_char();
+                assert!(self.consume_char() == '=');
+                Some(AttrOperator::DashMatch)
+            }
+            c => panic!("Unexpected character in attribute selector: {:?}", c),
+        };
+
+        let value = operator.map(|_| {
+            self.consume_whitespace();
+            let value = self.parse_attr_selector_value();
+            self.consume_whitespace();
+            value
+        });
+
+        assert!(self.consume_char() == ']');
+
+        AttrSelector {
+            name,
+            operator,
+            value,
+        }
+    }
+
+    /// Parse a quoted or bare attribute selector value
+    fn parse_attr_selector_value(&mut self) -> String {
+        match self.peek_char() {
+            Some(quote @ ('"' | '\'')) => {
+                self.consume_char();
+                let value = self.consume_while(|c| c != quote);
+                assert!(self.consume_char() == quote);
+                value
+            }
+            _ => self.parse_identifier(),
+        }
+    }
+
+    /// Parse a `:first-child`, `:last-child` or `:nth-child(an+b)` selector;
+    /// the leading `:` has already been consumed
+    fn parse_pseudo_class(&mut self) -> PseudoClass {
+        let name = self.parse_identifier();
+        match &*name.to_lowercase() {
+            "first-child" => PseudoClass::First,
+            "last-child" => PseudoClass::Last,
+            "nth-child" => {
+                assert!(self.consume_char() == '(');
+                self.consume_whitespace();
+                let (a, b) = self.parse_nth_expression();
+                self.consume_whitespace();
+                assert!(self.consume_char() == ')');
+                PseudoClass::Nth(a, b)
+            }
+            other => panic!("unrecognized pseudo-class: {}", other),
+        }
+    }
+
+    /// Parse the `an+b` microsyntax (`odd`, `even`, `n`, `2n`, `2n+1`,
+    /// `-n+3`, a bare integer, ...) into its `(a, b)` coefficients
+    fn parse_nth_expression(&mut self) -> (i32, i32) {
+        if self.starts_with_ignore_case("odd") {
+            self.consume_while(valid_identifier_char);
+            return (2, 1);
+        }
+        if self.starts_with_ignore_case("even") {
+            self.consume_while(valid_identifier_char);
+            return (2, 0);
+        }
+
+        let sign = self.parse_sign();
+        let digits = self.consume_while(|c| c.is_ascii_digit());
+        self.consume_whitespace();
+
+        if matches!(self.peek_char(), Some('n' | 'N')) {
+            self.consume_char();
+            let a = sign * digits.parse::<i32>().unwrap_or(1);
+
+            self.consume_whitespace();
+            let b = match self.peek_char() {
+                Some('+' | '-') => {
+                    let b_sign = self.parse_sign();
+                    self.consume_whitespace();
+                    let b_digits = self.consume_while(|c| c.is_ascii_digit());
+                    b_sign * b_digits.parse::<i32>().unwrap()
+                }
+                _ => 0,
+            };
+            (a, b)
+        } else {
+            let b = sign * digits.parse::<i32>().unwrap();
+            (0, b)
+        }
+    }
+
+    fn parse_sign(&mut self) -> i32 {
+        match self.peek_char() {
+            Some('-') => {
+                self.consume_char();
+                -1
+            }
+            Some('+') => {
+                self.consume_char();
+                1
+            }
+            _ => 1,
+        }
+    }
+
+    /// Case-insensitive `starts_with`, used for the `odd`/`even` keywords
+    fn starts_with_ignore_case(&self, s: &str) -> bool {
+        self.input
+            .get(self.pos..)
+            .is_some_and(|rest| rest.len() >= s.len() && rest[..s.len()].eq_ignore_ascii_case(s))
+    }
+
     fn parse_identifier(&mut self) -> String {
         self.consume_while(valid_identifier_char)
     }
 
-    // fn parse_rule(&mut self) -> Rule {
-    //     Rule {
-    //         selectors: self.parse_selectors(),
-    //         declarations: self.parse_declarations(),
-    //     }
-    // }
+    fn parse_rule(&mut self) -> Rule {
+        Rule {
+            selectors: self.parse_selectors(),
+            declarations: self.parse_declarations(),
+        }
+    }
+
+    /// Parse a sequence of rules until the input is exhausted
+    fn parse_rules(&mut self) -> Vec<Rule> {
+        let mut rules = Vec::new();
+        loop {
+            self.consume_whitespace();
+            if self.eof() {
+                break;
+            }
+            rules.push(self.parse_rule());
+        }
+        rules
+    }
 
     /// Parse comma separated list of selectors
     fn parse_selectors(&mut self) -> Vec<Selector> {
         let mut selectors = Vec::new();
         loop {
-            selectors.push(Selector::Simple(self.parse_simple_selector()));
+            selectors.push(self.parse_selector());
             self.consume_whitespace();
             match self.next_char() {
                 ',' => {
@@ -170,6 +415,217 @@ impl Css {
         selectors.sort_by(|a, b| b.specificity().cmp(&a.specificity()));
         selectors
     }
+
+    /// Parse a single (possibly compound) selector: a sequence of compound
+    /// selectors joined by a descendant (whitespace), child (`>`), adjacent
+    /// sibling (`+`) or general sibling (`~`) combinator
+    fn parse_selector(&mut self) -> Selector {
+        let mut parts = vec![(self.parse_simple_selector(), Combinator::Descendant)];
+
+        loop {
+            let start = self.pos;
+            self.consume_whitespace();
+            let had_whitespace = self.pos != start;
+
+            let combinator = match self.peek_char() {
+                Some('>') => {
+                    self.consume_char();
+                    self.consume_whitespace();
+                    Some(Combinator::Child)
+                }
+                Some('+') => {
+                    self.consume_char();
+                    self.consume_whitespace();
+                    Some(Combinator::Adjacent)
+                }
+                Some('~') => {
+                    self.consume_char();
+                    self.consume_whitespace();
+                    Some(Combinator::Sibling)
+                }
+                Some(',') | Some('{') | None => None,
+                Some(_) if had_whitespace => Some(Combinator::Descendant),
+                Some(c) => panic!("Unexpected character: {} in selector", c),
+            };
+
+            match combinator {
+                Some(combinator) => parts.push((self.parse_simple_selector(), combinator)),
+                None => break,
+            }
+        }
+
+        if parts.len() == 1 {
+            Selector::Simple(parts.pop().unwrap().0)
+        } else {
+            Selector::Complex(parts)
+        }
+    }
+
+    /// Parse a `{ name: value; ... }` block
+    fn parse_declarations(&mut self) -> Vec<Declaration> {
+        assert!(self.consume_char() == '{');
+        let mut declarations = Vec::new();
+        loop {
+            self.consume_whitespace();
+            if self.next_char() == '}' {
+                self.consume_char();
+                break;
+            }
+            declarations.push(self.parse_declaration());
+        }
+        declarations
+    }
+
+    fn parse_declaration(&mut self) -> Declaration {
+        let name = self.parse_identifier();
+        self.consume_whitespace();
+        assert!(self.consume_char() == ':');
+        self.consume_whitespace();
+        let value = self.parse_value();
+        self.consume_whitespace();
+        // the `;` is optional on the last declaration in a block
+        if !self.eof() && self.next_char() == ';' {
+            self.consume_char();
+        }
+        Declaration { name, value }
+    }
+
+    fn parse_value(&mut self) -> Value {
+        match self.next_char() {
+            '0'..='9' => self.parse_length(),
+            '#' => self.parse_hex_color(),
+            _ => self.parse_identifier_or_function(),
+        }
+    }
+
+    fn parse_length(&mut self) -> Value {
+        let num = self.parse_float();
+        let unit = self.parse_unit();
+        Value::Length(num, unit)
+    }
+
+    fn parse_float(&mut self) -> f32 {
+        let s = self.consume_while(|c| matches!(c, '0'..='9' | '.'));
+        s.parse().unwrap()
+    }
+
+    fn parse_unit(&mut self) -> Unit {
+        match &*self.parse_identifier().to_lowercase() {
+            "px" => Unit::Px,
+            u => panic!("unrecognized css unit: {}", u),
+        }
+    }
+
+    /// Parse a `#rrggbb` or `#rgb` hex color, defaulting alpha to 255
+    fn parse_hex_color(&mut self) -> Value {
+        assert!(self.consume_char() == '#');
+        let hex = self.consume_while(|c| c.is_ascii_hexdigit());
+        let color = match hex.len() {
+            3 => Color {
+                r: hex_pair(&hex[0..1].repeat(2)),
+                g: hex_pair(&hex[1..2].repeat(2)),
+                b: hex_pair(&hex[2..3].repeat(2)),
+                a: 255,
+            },
+            6 => Color {
+                r: hex_pair(&hex[0..2]),
+                g: hex_pair(&hex[2..4]),
+                b: hex_pair(&hex[4..6]),
+                a: 255,
+            },
+            _ => panic!("invalid hex color: #{}", hex),
+        };
+        Value::ColorValue(color)
+    }
+
+    /// A bare identifier is a keyword; an identifier followed by `(` is a
+    /// function call such as `rgb(...)`/`rgba(...)`
+    fn parse_identifier_or_function(&mut self) -> Value {
+        let name = self.parse_identifier();
+        if !self.eof() && self.next_char() == '(' {
+            self.parse_function_value(&name)
+        } else {
+            Value::Keyword(name)
+        }
+    }
+
+    fn parse_function_value(&mut self, name: &str) -> Value {
+        match &*name.to_lowercase() {
+            "rgb" | "rgba" => self.parse_rgba_function(),
+            other => panic!("unrecognized css function: {}", other),
+        }
+    }
+
+    /// Parse `rgb(r, g, b)` / `rgba(r, g, b, a)`, defaulting alpha to 255
+    fn parse_rgba_function(&mut self) -> Value {
+        assert!(self.consume_char() == '(');
+        let r = self.parse_color_component();
+        self.consume_comma();
+        let g = self.parse_color_component();
+        self.consume_comma();
+        let b = self.parse_color_component();
+        self.consume_whitespace();
+        let a = if self.next_char() == ',' {
+            self.consume_comma();
+            (self.parse_float() * 255.0) as u8
+        } else {
+            255
+        };
+        self.consume_whitespace();
+        assert!(self.consume_char() == ')');
+        Value::ColorValue(Color { r, g, b, a })
+    }
+
+    /// Parse one numeric argument of a color function
+    fn parse_color_component(&mut self) -> u8 {
+        self.consume_whitespace();
+        self.parse_float() as u8
+    }
+
+    fn consume_comma(&mut self) {
+        self.consume_whitespace();
+        assert!(self.consume_char() == ',');
+        self.consume_whitespace();
+    }
+
+    /// Parse a stylesheet's worth of rules
+    pub fn parse(source: String) -> Stylesheet {
+        let mut parser = Css { pos: 0, input: source };
+        Stylesheet {
+            rules: parser.parse_rules(),
+        }
+    }
+
+    /// Parse a comma-separated selector list from its own string, rather than
+    /// as part of a stylesheet rule - used for ad hoc dom queries. Unlike
+    /// `parse_selectors`, there's no `{` to stop at; parsing runs until eof,
+    /// and any leftover, unparseable input is a hard error rather than a
+    /// silently dropped selector.
+    pub fn parse_one(source: String) -> Vec<Selector> {
+        let mut parser = Css { pos: 0, input: source };
+        let selectors = parser.parse_selector_list();
+        assert!(parser.eof());
+        selectors
+    }
+
+    fn parse_selector_list(&mut self) -> Vec<Selector> {
+        let mut selectors = Vec::new();
+        loop {
+            selectors.push(self.parse_selector());
+            self.consume_whitespace();
+            if self.eof() {
+                break;
+            }
+            assert!(self.consume_char() == ',');
+            self.consume_whitespace();
+        }
+        selectors
+    }
+}
+
+/// Parse a 2-character hex string into its numeric value
+fn hex_pair(s: &str) -> u8 {
+    u8::from_str_radix(s, 16).unwrap()
 }
 
 fn valid_identifier_char(c: char) -> bool {
@@ -224,28 +680,360 @@ mod tests {
         let selectors = css.parse_selectors();
 
         // test 1st selector
-        match selectors.get(0).unwrap() {
+        match selectors.first().unwrap() {
             Selector::Simple(s1) => {
                 assert!(s1.id.as_ref().unwrap() == "test_id");
             }
+            _ => panic!("expected a simple selector"),
         }
         // test 2nd selector
         match selectors.get(1).unwrap() {
             Selector::Simple(s2) => {
-                assert!(s2.class.get(0).unwrap() == "test_class1");
+                assert!(s2.class.first().unwrap() == "test_class1");
             }
+            _ => panic!("expected a simple selector"),
         }
         // test 3rd selector
         match selectors.get(2).unwrap() {
             Selector::Simple(s3) => {
-                assert!(s3.class.get(0).unwrap() == "test_class2");
+                assert!(s3.class.first().unwrap() == "test_class2");
             }
+            _ => panic!("expected a simple selector"),
         }
         // test 4th selector
         match selectors.get(3).unwrap() {
             Selector::Simple(s4) => {
                 assert!(s4.tag_name.as_ref().unwrap() == "p");
             }
+            _ => panic!("expected a simple selector"),
         }
     }
+
+    #[test]
+    fn test_parse_descendant_selector() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("div p"),
+        };
+        match css.parse_selector() {
+            Selector::Complex(parts) => {
+                assert!(parts.len() == 2);
+                assert!(parts[0].0.tag_name.as_deref() == Some("div"));
+                assert!(parts[1].0.tag_name.as_deref() == Some("p"));
+                assert!(parts[1].1 == Combinator::Descendant);
+            }
+            _ => panic!("expected a complex selector"),
+        }
+    }
+
+    #[test]
+    fn test_parse_child_selector() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("ul > li"),
+        };
+        match css.parse_selector() {
+            Selector::Complex(parts) => {
+                assert!(parts[1].0.tag_name.as_deref() == Some("li"));
+                assert!(parts[1].1 == Combinator::Child);
+            }
+            _ => panic!("expected a complex selector"),
+        }
+    }
+
+    #[test]
+    fn test_parse_adjacent_and_sibling_selectors() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("h1 + p"),
+        };
+        match css.parse_selector() {
+            Selector::Complex(parts) => assert!(parts[1].1 == Combinator::Adjacent),
+            _ => panic!("expected a complex selector"),
+        }
+
+        let mut css = Css {
+            pos: 0,
+            input: String::from("h1~p"),
+        };
+        match css.parse_selector() {
+            Selector::Complex(parts) => assert!(parts[1].1 == Combinator::Sibling),
+            _ => panic!("expected a complex selector"),
+        }
+    }
+
+    #[test]
+    fn test_complex_selector_specificity_sums_parts() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("#main .box p"),
+        };
+        let selector = css.parse_selector();
+        assert!(selector.specificity() == (1, 1, 1));
+    }
+
+    #[test]
+    fn test_parse_attr_selector_existence() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("a[href]"),
+        };
+        let selector = css.parse_simple_selector();
+        assert!(selector.tag_name.as_deref() == Some("a"));
+        assert!(selector.attributes.len() == 1);
+        assert!(selector.attributes[0].name == "href");
+        assert!(selector.attributes[0].operator.is_none());
+    }
+
+    #[test]
+    fn test_parse_attr_selector_equals_quoted() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("input[type=\"checkbox\"]"),
+        };
+        let selector = css.parse_simple_selector();
+        let attr = &selector.attributes[0];
+        assert!(attr.name == "type");
+        assert!(attr.operator == Some(AttrOperator::Equals));
+        assert!(attr.value.as_deref() == Some("checkbox"));
+    }
+
+    #[test]
+    fn test_parse_attr_selector_operators() {
+        let cases = [
+            ("[href^=http]", AttrOperator::Prefix),
+            ("[href$=pdf]", AttrOperator::Suffix),
+            ("[href*=example]", AttrOperator::Substring),
+            ("[class~=active]", AttrOperator::Includes),
+            ("[lang|=en]", AttrOperator::DashMatch),
+        ];
+        for (input, expected) in cases {
+            let mut css = Css {
+                pos: 0,
+                input: input.to_string(),
+            };
+            let selector = css.parse_simple_selector();
+            assert!(selector.attributes[0].operator == Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_attr_selector_counts_toward_class_specificity() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("a[href]"),
+        };
+        let selector = Selector::Simple(css.parse_simple_selector());
+        assert!(selector.specificity() == (0, 1, 1));
+    }
+
+    #[test]
+    fn test_parse_pseudo_class_first_and_last_child() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("li:first-child"),
+        };
+        let selector = css.parse_simple_selector();
+        assert!(selector.pseudo_classes == vec![PseudoClass::First]);
+
+        let mut css = Css {
+            pos: 0,
+            input: String::from("li:last-child"),
+        };
+        let selector = css.parse_simple_selector();
+        assert!(selector.pseudo_classes == vec![PseudoClass::Last]);
+    }
+
+    #[test]
+    fn test_parse_nth_child_microsyntax() {
+        let cases = [
+            ("li:nth-child(odd)", (2, 1)),
+            ("li:nth-child(even)", (2, 0)),
+            ("li:nth-child(3)", (0, 3)),
+            ("li:nth-child(n)", (1, 0)),
+            ("li:nth-child(2n)", (2, 0)),
+            ("li:nth-child(2n+1)", (2, 1)),
+            ("li:nth-child(-n+3)", (-1, 3)),
+        ];
+        for (input, expected) in cases {
+            let mut css = Css {
+                pos: 0,
+                input: input.to_string(),
+            };
+            let selector = css.parse_simple_selector();
+            assert!(selector.pseudo_classes == vec![PseudoClass::Nth(expected.0, expected.1)]);
+        }
+    }
+
+    #[test]
+    fn test_pseudo_class_counts_toward_class_specificity() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("li:first-child"),
+        };
+        let selector = Selector::Simple(css.parse_simple_selector());
+        assert!(selector.specificity() == (0, 1, 1));
+    }
+
+    #[test]
+    fn test_parse_one_returns_every_selector_in_a_comma_separated_list() {
+        let selectors = Css::parse_one("li, p".to_string());
+        assert!(selectors.len() == 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_parse_one_panics_on_trailing_garbage() {
+        Css::parse_one("li]".to_string());
+    }
+
+    #[test]
+    fn test_parse_value_keyword() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("block"),
+        };
+        assert!(css.parse_value() == Value::Keyword("block".to_string()));
+    }
+
+    #[test]
+    fn test_parse_value_length() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("12.5px"),
+        };
+        assert!(css.parse_value() == Value::Length(12.5, Unit::Px));
+    }
+
+    #[test]
+    fn test_parse_value_hex_color_long() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("#ff0080"),
+        };
+        assert!(
+            css.parse_value()
+                == Value::ColorValue(Color {
+                    r: 255,
+                    g: 0,
+                    b: 128,
+                    a: 255,
+                })
+        );
+    }
+
+    #[test]
+    fn test_parse_value_hex_color_short() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("#f08"),
+        };
+        assert!(
+            css.parse_value()
+                == Value::ColorValue(Color {
+                    r: 255,
+                    g: 0,
+                    b: 136,
+                    a: 255,
+                })
+        );
+    }
+
+    #[test]
+    fn test_parse_value_rgb_function() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("rgb(255, 0, 128)"),
+        };
+        assert!(
+            css.parse_value()
+                == Value::ColorValue(Color {
+                    r: 255,
+                    g: 0,
+                    b: 128,
+                    a: 255,
+                })
+        );
+    }
+
+    #[test]
+    fn test_parse_value_rgba_function() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("rgba(255, 0, 128, 0.5)"),
+        };
+        assert!(
+            css.parse_value()
+                == Value::ColorValue(Color {
+                    r: 255,
+                    g: 0,
+                    b: 128,
+                    a: 127,
+                })
+        );
+    }
+
+    #[test]
+    fn test_parse_declaration() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("margin: 10px;"),
+        };
+        let decl = css.parse_declaration();
+        assert!(decl.name == "margin");
+        assert!(decl.value == Value::Length(10.0, Unit::Px));
+    }
+
+    #[test]
+    fn test_parse_declaration_without_trailing_semicolon() {
+        let mut css = Css {
+            pos: 0,
+            input: String::from("margin: 10px"),
+        };
+        let decl = css.parse_declaration();
+        assert!(decl.name == "margin");
+        assert!(decl.value == Value::Length(10.0, Unit::Px));
+    }
+
+    #[test]
+    fn test_parse_rule_with_no_semicolon_before_closing_brace() {
+        let source = "p { color: red }".to_string();
+        let stylesheet = Css::parse(source);
+
+        assert!(stylesheet.rules.len() == 1);
+        let rule = &stylesheet.rules[0];
+        assert!(rule.declarations.len() == 1);
+        assert!(rule.declarations[0].name == "color");
+    }
+
+    #[test]
+    fn test_parse_full_stylesheet() {
+        let source = "
+            h1, h2, .banner {
+                margin: 10px;
+                color: #cc0000;
+            }
+            * {
+                display: block;
+            }
+        "
+        .to_string();
+        let stylesheet = Css::parse(source);
+
+        assert!(stylesheet.rules.len() == 2);
+
+        let first = &stylesheet.rules[0];
+        assert!(first.selectors.len() == 3);
+        assert!(first.declarations.len() == 2);
+        assert!(first.declarations[0].name == "margin");
+        assert!(first.declarations[1].value == Value::ColorValue(Color {
+            r: 0xcc,
+            g: 0,
+            b: 0,
+            a: 255,
+        }));
+
+        let second = &stylesheet.rules[1];
+        assert!(second.declarations[0].value == Value::Keyword("block".to_string()));
+    }
 }
@@ -0,0 +1,502 @@
+use std::usize;
+
+use crate::dom::{AttrMap, Node};
+
+/// Elements that never have content or a closing tag
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param",
+    "source", "track", "wbr",
+];
+
+fn is_void_element(tag_name: &str) -> bool {
+    VOID_ELEMENTS.contains(&tag_name.to_lowercase().as_str())
+}
+
+/// Elements that implicitly close a still-open element of the same name
+/// instead of nesting inside it, e.g. `<li>a<li>b` being two sibling `<li>`s
+const IMPLIED_CLOSE_ON_SELF: [&str; 2] = ["li", "p"];
+
+pub struct Html {
+    pos: usize,
+    input: String,
+}
+
+impl Html {
+    /// Read the current character with out consuming it
+    fn next_char(&self) -> char {
+        self.input.get(self.pos..).unwrap().chars().next().unwrap()
+    }
+
+    /// Do the next characters start with the given string
+    fn starts_with(&self, s: &str) -> bool {
+        self.input.get(self.pos..).unwrap().starts_with(s)
+    }
+
+    /// Return true if input is consumed
+    fn eof(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    fn consume_char(&mut self) -> char {
+        let mut iter = self.input.get(self.pos..).unwrap().char_indices();
+        let (_, curr_char) = iter.next().unwrap();
+        let (next_pos, _) = iter.next().unwrap_or((1, ' '));
+        self.pos += next_pos;
+        curr_char
+    }
+
+    fn consume_while<F>(&mut self, test: F) -> String
+    where
+        F: Fn(char) -> bool,
+    {
+        let mut result = String::new();
+        while !self.eof() && test(self.next_char()) {
+            result.push(self.consume_char());
+        }
+        result
+    }
+
+    fn consume_whitespace(&mut self) {
+        self.consume_while(char::is_whitespace);
+    }
+
+    /// Parse a tag or attribute name
+    fn parse_tag_name(&mut self) -> String {
+        self.consume_while(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' => true,
+            _ => false,
+        })
+    }
+
+    fn parse_node(&mut self, open_tags: &mut Vec<String>) -> Option<Node> {
+        if self.starts_with("<!--") {
+            self.parse_comment();
+            return None;
+        } else if self.starts_with("</") {
+            return None;
+        }
+        match self.next_char() {
+            '<' => Some(self.parse_element(open_tags)),
+            _ => Some(self.parse_text()),
+        }
+    }
+
+    fn parse_text(&mut self) -> Node {
+        let mut data = String::new();
+        loop {
+            let text = self.consume_while(|c| c != '<');
+            data.push_str(&text);
+            if self.starts_with("<!--") {
+                self.parse_comment();
+            } else {
+                break;
+            }
+        }
+        Node::new_text(decode_entities(&data))
+    }
+
+    fn parse_element(&mut self, open_tags: &mut Vec<String>) -> Node {
+        // parse opening tag
+        assert!(self.consume_char() == '<');
+        let tag_name = self.parse_tag_name();
+        let attrs = self.parse_attributes();
+
+        let self_closing = matches!(self.next_char(), '/');
+        if self_closing {
+            self.consume_char();
+        }
+        assert!(self.consume_char() == '>');
+
+        if self_closing || is_void_element(&tag_name) {
+            return Node::new_element(tag_name, attrs, Vec::new());
+        }
+
+        // get contents
+        open_tags.push(tag_name.clone());
+        let children = self.parse_nodes(open_tags);
+        open_tags.pop();
+
+        self.consume_closing_tag(&tag_name);
+
+        Node::new_element(tag_name, attrs, children)
+    }
+
+    /// Consume this element's closing tag if the next tag is one. A closing
+    /// tag that names some other element (or no closing tag at all, e.g. at
+    /// eof) means `tag_name` was implicitly closed - left untouched so an
+    /// enclosing `parse_element` call can match it instead.
+    fn consume_closing_tag(&mut self, tag_name: &str) {
+        if !self.starts_with("</") {
+            return;
+        }
+        let saved_pos = self.pos;
+        self.consume_char();
+        self.consume_char();
+        let close_name = self.parse_tag_name();
+        self.consume_whitespace();
+        if close_name.eq_ignore_ascii_case(tag_name) && !self.eof() && self.next_char() == '>' {
+            self.consume_char();
+        } else {
+            self.pos = saved_pos;
+        }
+    }
+
+    /// The tag name of the closing tag starting at the current position,
+    /// without consuming anything
+    fn peek_closing_tag_name(&self) -> String {
+        let after_slash = self.pos + 2;
+        self.input
+            .get(after_slash..)
+            .unwrap()
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect()
+    }
+
+    /// The tag name of the opening tag starting at the current position,
+    /// without consuming anything
+    fn peek_opening_tag_name(&self) -> String {
+        let after_lt = self.pos + 1;
+        self.input
+            .get(after_lt..)
+            .unwrap()
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect()
+    }
+
+    /// Whether the opening tag at the current position should implicitly
+    /// close the innermost open element rather than nest inside it - e.g. a
+    /// second `<li>` while a `<li>` is still open
+    fn implicitly_closes_innermost(&self, open_tags: &[String]) -> bool {
+        let Some(innermost) = open_tags.last() else {
+            return false;
+        };
+        if !IMPLIED_CLOSE_ON_SELF
+            .iter()
+            .any(|tag| tag.eq_ignore_ascii_case(innermost))
+        {
+            return false;
+        }
+        self.peek_opening_tag_name().eq_ignore_ascii_case(innermost)
+    }
+
+    /// Consume a closing tag that names no currently open element - html5's
+    /// recovery for e.g. a leftover `</input>` after a void element - and
+    /// discard it instead of letting it masquerade as some ancestor's close
+    fn discard_stray_closing_tag(&mut self) {
+        self.consume_char();
+        self.consume_char();
+        self.parse_tag_name();
+        self.consume_whitespace();
+        if !self.eof() && self.next_char() == '>' {
+            self.consume_char();
+        }
+    }
+
+    /// Parse single key value attr pair
+    fn parse_attr(&mut self) -> (String, String) {
+        let key = self.parse_tag_name();
+        assert!(self.consume_char() == '=');
+        let value = self.parse_attr_value();
+        (key, value)
+    }
+
+    fn parse_attr_value(&mut self) -> String {
+        let open_quote = self.consume_char();
+        assert!(open_quote == '"' || open_quote == '\'');
+        let value = self.consume_while(|c| c != open_quote);
+        assert!(self.consume_char() == open_quote);
+        decode_entities(&value)
+    }
+
+    fn parse_attributes(&mut self) -> AttrMap {
+        let mut attributes = AttrMap::new();
+        loop {
+            self.consume_whitespace();
+            if matches!(self.next_char(), '>' | '/') {
+                break;
+            }
+            let (key, value) = self.parse_attr();
+            attributes.insert(key, value);
+        }
+        attributes
+    }
+
+    fn parse_comment(&mut self) {
+        // consume the comment opening
+        for _ in 0..4 {
+            self.consume_char();
+        }
+        // look for comment closing
+        while !self.starts_with("-->") {
+            self.consume_char();
+        }
+
+        // consume the comment close
+        for _ in 0..3 {
+            self.consume_char();
+        }
+    }
+
+    /// Parse child nodes recursively. `open_tags` is the stack of ancestor
+    /// tag names currently open, innermost last; a closing tag naming none of
+    /// them is a stray (e.g. a leftover `</input>` after a void element) and
+    /// is discarded rather than mistaken for an ancestor's close. An opening
+    /// tag that implicitly closes the innermost element (e.g. a second
+    /// `<li>`) also ends this call, the same as an explicit closing tag would.
+    fn parse_nodes(&mut self, open_tags: &mut Vec<String>) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        loop {
+            self.consume_whitespace();
+            // check for comment node which should not be added to nodes
+            if self.starts_with("<!--") {
+                self.parse_comment();
+            } else if self.eof() {
+                break;
+            } else if self.starts_with("</") {
+                let name = self.peek_closing_tag_name();
+                if open_tags.iter().any(|tag| tag.eq_ignore_ascii_case(&name)) {
+                    break;
+                }
+                self.discard_stray_closing_tag();
+                continue;
+            } else if self.next_char() == '<' && self.implicitly_closes_innermost(open_tags) {
+                break;
+            }
+            match self.parse_node(open_tags) {
+                Some(node) => nodes.push(node),
+                None => continue,
+            }
+        }
+        nodes
+    }
+
+    /// Parse an html document and return the root node
+    pub fn parse(source: String) -> Node {
+        let mut nodes = Html {
+            pos: 0,
+            input: source,
+        }
+        .parse_nodes(&mut Vec::new());
+
+        // if there is only one node, return it
+        if nodes.len() == 1 {
+            nodes.swap_remove(0)
+        } else {
+            Node::new_element("html".to_string(), AttrMap::new(), nodes)
+        }
+    }
+}
+
+/// Decode `&amp;`, `&lt;`, `&gt;`, `&quot;` and numeric character references
+/// (`&#nn;`, `&#xhh;`) in `raw`; any other `&...;` sequence, or an unterminated
+/// `&`, is passed through unchanged.
+fn decode_entities(raw: &str) -> String {
+    let mut result = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '&' {
+            result.push(c);
+            continue;
+        }
+
+        let mut name = String::new();
+        let mut terminated = false;
+        while let Some(&next) = chars.peek() {
+            if next == ';' {
+                chars.next();
+                terminated = true;
+                break;
+            }
+            if !next.is_alphanumeric() && next != '#' {
+                break;
+            }
+            name.push(next);
+            chars.next();
+        }
+
+        match terminated.then(|| decode_entity(&name)).flatten() {
+            Some(decoded) => result.push(decoded),
+            None => {
+                result.push('&');
+                result.push_str(&name);
+                if terminated {
+                    result.push(';');
+                }
+            }
+        }
+    }
+    result
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        _ if name.starts_with("#x") || name.starts_with("#X") => {
+            u32::from_str_radix(&name[2..], 16).ok().and_then(char::from_u32)
+        }
+        _ if name.starts_with('#') => name[1..].parse::<u32>().ok().and_then(char::from_u32),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let html = "<h1>Hello, <i>world!</i></h1>".to_string();
+        let test_dom = Html::parse(html);
+
+        // ensure the root node has the correct number of children
+        assert!(test_dom.children().len() == 2);
+
+        // ensure the root node is the correct type
+        assert!(test_dom.node_type().unwrap() == "element".to_string());
+        let tag = test_dom.get_tag().unwrap();
+        assert!(&tag == "h1");
+
+        // ensure first child is a text node
+        let first_child = test_dom.children().get(0).unwrap();
+        assert!(first_child.node_type().unwrap() == "text".to_string());
+        // test text content
+        let expected = "Hello, ".to_string();
+        assert!(first_child.get_text().unwrap() == expected);
+
+        // test second child
+        let second_child = test_dom.children().get(1).unwrap();
+
+        // test that second child is an i tag
+        let tag = second_child.get_tag().unwrap();
+        assert!(&tag == "i");
+
+        // test that second child has a child
+        assert!(!second_child.children().is_empty());
+
+        // test the grand child
+        let grand_child = second_child.children().get(0).unwrap();
+        assert!(&grand_child.node_type().unwrap() == "text");
+        let expected = "world!";
+        assert!(&grand_child.get_text().unwrap() == expected);
+    }
+
+    #[test]
+    fn test_parse_comment_text() {
+        let html = "<h1>Hello <!-- this is a comment --> world</h1>".to_string();
+        let root = Html::parse(html);
+
+        // Ensure the root node only has one child, the text within h1 tags
+        assert!(root.children().len() == 1);
+
+        // Ensure the text only contains the full text with comment removed
+        let child = root.children().get(0).unwrap();
+        assert!(&child.get_text().unwrap() == "Hello  world");
+    }
+
+    #[test]
+    fn test_parse_comment_node() {
+        let html = "<h1><!-- comment --></h1>".to_string();
+        let root = Html::parse(html);
+
+        // assert that only one node was parsed
+        assert!(root.children().is_empty());
+
+        // assert the h1 tag is parsed properly
+        assert!(&root.get_tag().unwrap() == "h1");
+    }
+
+    #[test]
+    fn test_void_element_has_no_children_and_needs_no_closing_tag() {
+        let html = "<div><img src=\"pic.png\"><p>text</p></div>".to_string();
+        let root = Html::parse(html);
+
+        assert!(&root.get_tag().unwrap() == "div");
+        assert!(root.children().len() == 2);
+
+        let img = root.children().get(0).unwrap();
+        assert!(&img.get_tag().unwrap() == "img");
+        assert!(img.children().is_empty());
+    }
+
+    #[test]
+    fn test_self_closing_slash_is_accepted() {
+        let html = "<div><br/><hr /></div>".to_string();
+        let root = Html::parse(html);
+
+        assert!(root.children().len() == 2);
+        assert!(&root.children().get(0).unwrap().get_tag().unwrap() == "br");
+        assert!(&root.children().get(1).unwrap().get_tag().unwrap() == "hr");
+    }
+
+    #[test]
+    fn test_missing_closing_tag_is_implicitly_closed_by_an_ancestors() {
+        let html = "<div><p>one<span>two</span></div>".to_string();
+        let root = Html::parse(html);
+
+        assert!(&root.get_tag().unwrap() == "div");
+        assert!(root.children().len() == 1);
+
+        let p = root.children().get(0).unwrap();
+        assert!(&p.get_tag().unwrap() == "p");
+        assert!(p.children().len() == 2);
+        assert!(&p.children().get(1).unwrap().get_tag().unwrap() == "span");
+    }
+
+    #[test]
+    fn test_unclosed_li_is_implicitly_closed_by_a_sibling_li() {
+        let html = "<ul><li>a<li>b<li>c</ul>".to_string();
+        let root = Html::parse(html);
+
+        assert!(&root.get_tag().unwrap() == "ul");
+        assert!(root.children().len() == 3);
+        for (i, expected_text) in ["a", "b", "c"].iter().enumerate() {
+            let li = root.children().get(i).unwrap();
+            assert!(&li.get_tag().unwrap() == "li");
+            assert!(li.children().len() == 1);
+            assert!(&li.children().get(0).unwrap().get_text().unwrap() == expected_text);
+        }
+    }
+
+    #[test]
+    fn test_unclosed_p_is_implicitly_closed_by_a_sibling_p() {
+        let html = "<div><p>one<p>two</div>".to_string();
+        let root = Html::parse(html);
+
+        assert!(root.children().len() == 2);
+        for child in root.children() {
+            assert!(&child.get_tag().unwrap() == "p");
+        }
+    }
+
+    #[test]
+    fn test_stray_closing_tag_after_void_element_is_discarded() {
+        let html = "<div><input type=\"text\"></input><p>after</p></div>".to_string();
+        let root = Html::parse(html);
+
+        assert!(root.children().len() == 2);
+        assert!(&root.children().get(0).unwrap().get_tag().unwrap() == "input");
+        assert!(&root.children().get(1).unwrap().get_tag().unwrap() == "p");
+    }
+
+    #[test]
+    fn test_entity_decoding_in_text() {
+        let html = "<p>Tom &amp; Jerry &lt;3 &#65;&#x42;</p>".to_string();
+        let root = Html::parse(html);
+
+        let text = root.children().get(0).unwrap().get_text().unwrap();
+        assert!(text == "Tom & Jerry <3 AB");
+    }
+
+    #[test]
+    fn test_entity_decoding_in_attribute_value() {
+        let html = "<a href=\"/a?x=1&amp;y=2\">link</a>".to_string();
+        let root = Html::parse(html);
+
+        assert!(root.attr("href").unwrap() == "/a?x=1&y=2");
+    }
+}
@@ -0,0 +1,624 @@
+use std::collections::HashMap;
+
+use crate::bloom::BloomFilter;
+use crate::dom::Node;
+use crate::nth::NthIndexCache;
+use crate::parser::css::{
+    AttrOperator, AttrSelector, Combinator, PseudoClass, Rule, Selector, SimpleSelector,
+    Specificity, Stylesheet, Value,
+};
+
+/// Map of a node's specified css properties, keyed by property name
+pub type PropertyMap = HashMap<String, Value>;
+
+/// A `Node` paired with the specified values that apply to it
+#[derive(Debug)]
+pub struct StyledNode<'a> {
+    pub node: &'a Node,
+    pub specified_values: PropertyMap,
+    pub children: Vec<StyledNode<'a>>,
+}
+
+impl<'a> StyledNode<'a> {
+    /// Look up the specified value of a css property
+    pub fn value(&self, name: &str) -> Option<&Value> {
+        self.specified_values.get(name)
+    }
+}
+
+type MatchedRule<'a> = (Specificity, &'a Rule);
+
+/// One level of ancestry while walking down the dom tree: the node at this
+/// level, together with the full sibling list it belongs to and its index
+/// within that list. Keeping the whole sibling slice (rather than just the
+/// preceding siblings) lets combinator matching look either direction.
+#[derive(Clone, Copy)]
+struct Frame<'a> {
+    node: &'a Node,
+    siblings: &'a [Node],
+    index: usize,
+}
+
+/// Depth-first, document-order search for every descendant of `root`
+/// (`root` itself excluded, matching `querySelectorAll`/nipper's `Find`)
+/// matching any of `selectors`, reusing the same ancestor/sibling path
+/// machinery `style_tree` uses for combinator matching
+pub(crate) fn select_all<'a>(root: &'a Node, selectors: &[Selector]) -> Vec<&'a Node> {
+    let root_frame = Frame {
+        node: root,
+        siblings: std::slice::from_ref(root),
+        index: 0,
+    };
+    let mut path = Vec::new();
+    let mut ancestors = BloomFilter::new();
+    let mut nth_cache = NthIndexCache::new();
+    let mut matched = Vec::new();
+    collect_matches(
+        root_frame,
+        true,
+        &mut path,
+        &mut ancestors,
+        &mut nth_cache,
+        selectors,
+        &mut matched,
+    );
+    matched
+}
+
+/// The first descendant of `root` (`root` itself excluded), in document
+/// order, matching any of `selectors`
+pub(crate) fn select<'a>(root: &'a Node, selectors: &[Selector]) -> Option<&'a Node> {
+    select_all(root, selectors).into_iter().next()
+}
+
+fn collect_matches<'a>(
+    frame: Frame<'a>,
+    is_root: bool,
+    path: &mut Vec<Frame<'a>>,
+    ancestors: &mut BloomFilter,
+    nth_cache: &mut NthIndexCache,
+    selectors: &[Selector],
+    out: &mut Vec<&'a Node>,
+) {
+    path.push(frame);
+    ancestors.insert_node(frame.node);
+
+    if !is_root
+        && frame.node.is_element()
+        && selectors
+            .iter()
+            .any(|selector| matches(path, ancestors, nth_cache, selector))
+    {
+        out.push(frame.node);
+    }
+
+    let children = frame.node.children();
+    for (i, child) in children.iter().enumerate() {
+        let child_frame = Frame {
+            node: child,
+            siblings: children,
+            index: i,
+        };
+        collect_matches(
+            child_frame, false, path, ancestors, nth_cache, selectors, out,
+        );
+    }
+
+    ancestors.remove_node(frame.node);
+    path.pop();
+}
+
+/// Apply a stylesheet to a dom tree, producing a tree of specified values
+pub fn style_tree<'a>(root: &'a Node, stylesheet: &'a Stylesheet) -> StyledNode<'a> {
+    let root_siblings = std::slice::from_ref(root);
+    let mut path = Vec::new();
+    let mut ancestors = BloomFilter::new();
+    let mut nth_cache = NthIndexCache::new();
+    build_styled_node(
+        root,
+        root_siblings,
+        0,
+        &mut path,
+        &mut ancestors,
+        &mut nth_cache,
+        stylesheet,
+    )
+}
+
+/// Recursively build a `StyledNode`, threading the ancestor path (used by
+/// descendant/child/sibling combinators) down through the tree, alongside a
+/// bloom filter of the same ancestors used to fast-reject descendant
+/// selectors before paying for the full walk
+fn build_styled_node<'a>(
+    node: &'a Node,
+    siblings: &'a [Node],
+    index: usize,
+    path: &mut Vec<Frame<'a>>,
+    ancestors: &mut BloomFilter,
+    nth_cache: &mut NthIndexCache,
+    stylesheet: &'a Stylesheet,
+) -> StyledNode<'a> {
+    path.push(Frame {
+        node,
+        siblings,
+        index,
+    });
+    ancestors.insert_node(node);
+
+    let specified_values = if node.is_element() {
+        specified_values(path, ancestors, nth_cache, stylesheet)
+    } else {
+        HashMap::new()
+    };
+
+    let children = node.children();
+    let styled_children = children
+        .iter()
+        .enumerate()
+        .map(|(i, child)| {
+            build_styled_node(child, children, i, path, ancestors, nth_cache, stylesheet)
+        })
+        .collect();
+
+    ancestors.remove_node(node);
+    path.pop();
+
+    StyledNode {
+        node,
+        specified_values,
+        children: styled_children,
+    }
+}
+
+/// Fold every matching rule's declarations into a single property map, with
+/// higher-specificity (and later) rules winning
+fn specified_values(
+    path: &[Frame],
+    ancestors: &BloomFilter,
+    nth_cache: &mut NthIndexCache,
+    stylesheet: &Stylesheet,
+) -> PropertyMap {
+    let mut values = HashMap::new();
+    let mut rules = matching_rules(path, ancestors, nth_cache, stylesheet);
+
+    // lowest specificity first, so later assignments in the loop win the cascade
+    rules.sort_by_key(|&(specificity, _)| specificity);
+
+    for (_, rule) in rules {
+        for declaration in &rule.declarations {
+            values.insert(declaration.name.clone(), declaration.value.clone());
+        }
+    }
+    values
+}
+
+fn matching_rules<'a>(
+    path: &[Frame],
+    ancestors: &BloomFilter,
+    nth_cache: &mut NthIndexCache,
+    stylesheet: &'a Stylesheet,
+) -> Vec<MatchedRule<'a>> {
+    stylesheet
+        .rules
+        .iter()
+        .filter_map(|rule| match_rule(path, ancestors, nth_cache, rule))
+        .collect()
+}
+
+/// A rule matches a node if any one of its selectors does
+fn match_rule<'a>(
+    path: &[Frame],
+    ancestors: &BloomFilter,
+    nth_cache: &mut NthIndexCache,
+    rule: &'a Rule,
+) -> Option<MatchedRule<'a>> {
+    rule.selectors
+        .iter()
+        .find(|selector| matches(path, ancestors, nth_cache, selector))
+        .map(|selector| (selector.specificity(), rule))
+}
+
+fn matches(
+    path: &[Frame],
+    ancestors: &BloomFilter,
+    nth_cache: &mut NthIndexCache,
+    selector: &Selector,
+) -> bool {
+    match selector {
+        Selector::Simple(simple) => matches_simple_selector(nth_cache, *path.last().unwrap(), simple),
+        Selector::Complex(parts) => matches_complex_selector(path, ancestors, nth_cache, parts),
+    }
+}
+
+/// Could `target` possibly be on the current ancestor path? A `false` here
+/// means it provably isn't, so a descendant combinator can fail immediately
+/// without walking the path; `true` only means the walk is still needed, since
+/// the filter can false-positive.
+fn could_match_ancestor(ancestors: &BloomFilter, target: &SimpleSelector) -> bool {
+    if let Some(tag) = &target.tag_name {
+        if !ancestors.might_contain(&tag.to_lowercase()) {
+            return false;
+        }
+    }
+    if let Some(id) = &target.id {
+        if !ancestors.might_contain(id) {
+            return false;
+        }
+    }
+    target.class.iter().all(|class| ancestors.might_contain(class))
+}
+
+/// The index of the nearest element preceding `index` in `siblings`, skipping
+/// over any text nodes in between - element adjacency isn't broken by
+/// whitespace or other inline text, per CSS semantics
+fn previous_element_index(siblings: &[Node], index: usize) -> Option<usize> {
+    (0..index).rev().find(|&i| siblings[i].is_element())
+}
+
+/// Match a sequence of compound selectors right-to-left, as a browser engine
+/// would: the rightmost part must match the node being styled, then each
+/// combinator moving leftward narrows the search to the parent (`>`), some
+/// ancestor (` `), the immediately preceding sibling (`+`), or some earlier
+/// sibling (`~`).
+fn matches_complex_selector(
+    path: &[Frame],
+    ancestors: &BloomFilter,
+    nth_cache: &mut NthIndexCache,
+    parts: &[(SimpleSelector, Combinator)],
+) -> bool {
+    let mut depth = path.len() - 1;
+    let mut siblings = path[depth].siblings;
+    let mut index = path[depth].index;
+
+    let mut part_idx = parts.len() - 1;
+    if !matches_simple_selector(nth_cache, path[depth], &parts[part_idx].0) {
+        return false;
+    }
+
+    while part_idx > 0 {
+        let combinator = parts[part_idx].1;
+        let target = &parts[part_idx - 1].0;
+
+        let matched = match combinator {
+            Combinator::Child => {
+                if depth == 0 {
+                    false
+                } else if matches_simple_selector(nth_cache, path[depth - 1], target) {
+                    depth -= 1;
+                    siblings = path[depth].siblings;
+                    index = path[depth].index;
+                    true
+                } else {
+                    false
+                }
+            }
+            Combinator::Descendant => {
+                if !could_match_ancestor(ancestors, target) {
+                    false
+                } else {
+                    let mut found = false;
+                    while depth > 0 {
+                        depth -= 1;
+                        if matches_simple_selector(nth_cache, path[depth], target) {
+                            siblings = path[depth].siblings;
+                            index = path[depth].index;
+                            found = true;
+                            break;
+                        }
+                    }
+                    found
+                }
+            }
+            Combinator::Adjacent => match previous_element_index(siblings, index) {
+                Some(prev_idx) => {
+                    let frame = Frame {
+                        node: &siblings[prev_idx],
+                        siblings,
+                        index: prev_idx,
+                    };
+                    matches_simple_selector(nth_cache, frame, target) && {
+                        index = prev_idx;
+                        true
+                    }
+                }
+                None => false,
+            },
+            Combinator::Sibling => {
+                let mut found = false;
+                let mut i = index;
+                while i > 0 {
+                    i -= 1;
+                    let frame = Frame {
+                        node: &siblings[i],
+                        siblings,
+                        index: i,
+                    };
+                    if matches_simple_selector(nth_cache, frame, target) {
+                        index = i;
+                        found = true;
+                        break;
+                    }
+                }
+                found
+            }
+        };
+
+        if !matched {
+            return false;
+        }
+        part_idx -= 1;
+    }
+
+    true
+}
+
+fn matches_simple_selector(nth_cache: &mut NthIndexCache, frame: Frame, selector: &SimpleSelector) -> bool {
+    let node = frame.node;
+    if !node.is_element() {
+        return false;
+    }
+
+    if selector
+        .tag_name
+        .as_ref()
+        .is_some_and(|tag| node.get_tag().as_deref() != Some(tag.as_str()))
+    {
+        return false;
+    }
+
+    if selector
+        .id
+        .as_ref()
+        .is_some_and(|id| node.id() != Some(id.as_str()))
+    {
+        return false;
+    }
+
+    let node_classes = node.classes();
+    if selector
+        .class
+        .iter()
+        .any(|class| !node_classes.contains(class.as_str()))
+    {
+        return false;
+    }
+
+    if selector
+        .attributes
+        .iter()
+        .any(|attr| !matches_attr_selector(node, attr))
+    {
+        return false;
+    }
+
+    if selector
+        .pseudo_classes
+        .iter()
+        .any(|pseudo| !matches_pseudo_class(nth_cache, frame, pseudo))
+    {
+        return false;
+    }
+
+    true
+}
+
+fn matches_pseudo_class(nth_cache: &mut NthIndexCache, frame: Frame, pseudo: &PseudoClass) -> bool {
+    let Some((position, count)) = nth_cache.position(frame.siblings, frame.index) else {
+        return false;
+    };
+
+    match pseudo {
+        PseudoClass::First => position == 1,
+        PseudoClass::Last => position == count,
+        PseudoClass::Nth(a, b) => nth_child_matches(*a, *b, position as i32),
+    }
+}
+
+/// Does the 1-based `position` satisfy `position = a*n + b` for some integer n >= 0?
+fn nth_child_matches(a: i32, b: i32, position: i32) -> bool {
+    if a == 0 {
+        return position == b;
+    }
+    let k = position - b;
+    k % a == 0 && k / a >= 0
+}
+
+fn matches_attr_selector(node: &Node, attr: &AttrSelector) -> bool {
+    let Some(actual) = node.attr(&attr.name) else {
+        return false;
+    };
+
+    match (attr.operator, &attr.value) {
+        (None, _) => true,
+        (Some(AttrOperator::Equals), Some(value)) => actual == value,
+        (Some(AttrOperator::Prefix), Some(value)) => actual.starts_with(value.as_str()),
+        (Some(AttrOperator::Suffix), Some(value)) => actual.ends_with(value.as_str()),
+        (Some(AttrOperator::Substring), Some(value)) => actual.contains(value.as_str()),
+        (Some(AttrOperator::Includes), Some(value)) => {
+            actual.split_whitespace().any(|word| word == value)
+        }
+        (Some(AttrOperator::DashMatch), Some(value)) => {
+            actual == value || actual.starts_with(&format!("{value}-"))
+        }
+        (Some(_), None) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::css::Css;
+    use crate::parser::html::Html;
+
+    #[test]
+    fn test_cascade_picks_more_specific_rule() {
+        let dom = Html::parse("<div id=\"main\" class=\"box\"></div>".to_string());
+        let stylesheet =
+            Css::parse(".box { color: #ff0000; } #main { color: #00ff00; }".to_string());
+
+        let styled = style_tree(&dom, &stylesheet);
+
+        assert!(
+            styled.value("color").unwrap()
+                == &Value::ColorValue(crate::parser::css::Color {
+                    r: 0,
+                    g: 255,
+                    b: 0,
+                    a: 255,
+                })
+        );
+    }
+
+    #[test]
+    fn test_unmatched_node_has_no_specified_values() {
+        let dom = Html::parse("<div></div>".to_string());
+        let stylesheet = Css::parse("p { color: #ff0000; }".to_string());
+
+        let styled = style_tree(&dom, &stylesheet);
+
+        assert!(styled.value("color").is_none());
+    }
+
+    #[test]
+    fn test_text_node_has_no_specified_values() {
+        let dom = Html::parse("<p>hello</p>".to_string());
+        let stylesheet = Css::parse("p { color: #ff0000; }".to_string());
+
+        let styled = style_tree(&dom, &stylesheet);
+        let text_child = &styled.children[0];
+
+        assert!(text_child.specified_values.is_empty());
+    }
+
+    #[test]
+    fn test_descendant_combinator_matches_nested_not_direct_only() {
+        let dom = Html::parse("<div><span><em>hi</em></span></div>".to_string());
+        let stylesheet = Css::parse("div em { color: #ff0000; }".to_string());
+
+        let styled = style_tree(&dom, &stylesheet);
+        let em = &styled.children[0].children[0];
+
+        assert!(em.value("color").is_some());
+    }
+
+    #[test]
+    fn test_child_combinator_rejects_grandchild() {
+        let dom = Html::parse("<div><span><em>hi</em></span></div>".to_string());
+        let stylesheet = Css::parse("div > em { color: #ff0000; }".to_string());
+
+        let styled = style_tree(&dom, &stylesheet);
+        let em = &styled.children[0].children[0];
+
+        assert!(em.value("color").is_none());
+    }
+
+    #[test]
+    fn test_adjacent_combinator_matches_only_immediate_sibling() {
+        let dom = Html::parse("<div><h1></h1><p></p><p></p></div>".to_string());
+        let stylesheet = Css::parse("h1 + p { color: #ff0000; }".to_string());
+
+        let styled = style_tree(&dom, &stylesheet);
+        let first_p = &styled.children[1];
+        let second_p = &styled.children[2];
+
+        assert!(first_p.value("color").is_some());
+        assert!(second_p.value("color").is_none());
+    }
+
+    #[test]
+    fn test_adjacent_combinator_skips_intervening_text_nodes() {
+        let dom = Html::parse("<div><h1>Title</h1>Some copy<p>body</p></div>".to_string());
+        let stylesheet = Css::parse("h1 + p { color: #ff0000; }".to_string());
+
+        let styled = style_tree(&dom, &stylesheet);
+        let p = &styled.children[2];
+
+        assert!(p.node.get_tag().as_deref() == Some("p"));
+        assert!(p.value("color").is_some());
+    }
+
+    #[test]
+    fn test_sibling_combinator_matches_any_later_sibling() {
+        let dom = Html::parse("<div><h1></h1><p></p><p></p></div>".to_string());
+        let stylesheet = Css::parse("h1 ~ p { color: #ff0000; }".to_string());
+
+        let styled = style_tree(&dom, &stylesheet);
+        let first_p = &styled.children[1];
+        let second_p = &styled.children[2];
+
+        assert!(first_p.value("color").is_some());
+        assert!(second_p.value("color").is_some());
+    }
+
+    #[test]
+    fn test_attr_selector_prefix_match() {
+        let dom = Html::parse(
+            "<div><a href=\"https://example.com\"></a><a href=\"mailto:me\"></a></div>".to_string(),
+        );
+        let stylesheet = Css::parse("a[href^=https] { color: #ff0000; }".to_string());
+
+        let styled = style_tree(&dom, &stylesheet);
+
+        assert!(styled.children[0].value("color").is_some());
+        assert!(styled.children[1].value("color").is_none());
+    }
+
+    #[test]
+    fn test_descendant_combinator_with_no_matching_ancestor_anywhere() {
+        let dom = Html::parse("<div><span><em>hi</em></span></div>".to_string());
+        let stylesheet = Css::parse("section em { color: #ff0000; }".to_string());
+
+        let styled = style_tree(&dom, &stylesheet);
+        let em = &styled.children[0].children[0];
+
+        assert!(em.value("color").is_none());
+    }
+
+    #[test]
+    fn test_first_and_last_child_pseudo_classes() {
+        let dom = Html::parse("<ul><li></li><li></li><li></li></ul>".to_string());
+        let stylesheet =
+            Css::parse("li:first-child { color: #ff0000; } li:last-child { color: #00ff00; }".to_string());
+
+        let styled = style_tree(&dom, &stylesheet);
+
+        assert!(styled.children[0].value("color").is_some());
+        assert!(styled.children[1].value("color").is_none());
+        assert!(styled.children[2].value("color").is_some());
+    }
+
+    #[test]
+    fn test_nth_child_pseudo_class_matches_every_other() {
+        let dom = Html::parse("<ul><li></li><li></li><li></li><li></li></ul>".to_string());
+        let stylesheet = Css::parse("li:nth-child(2n+1) { color: #ff0000; }".to_string());
+
+        let styled = style_tree(&dom, &stylesheet);
+
+        assert!(styled.children[0].value("color").is_some());
+        assert!(styled.children[1].value("color").is_none());
+        assert!(styled.children[2].value("color").is_some());
+        assert!(styled.children[3].value("color").is_none());
+    }
+
+    #[test]
+    fn test_nth_child_ignores_text_node_siblings() {
+        let dom = Html::parse("<ul>first<li></li>second<li></li></ul>".to_string());
+        let stylesheet = Css::parse("li:first-child { color: #ff0000; }".to_string());
+
+        let styled = style_tree(&dom, &stylesheet);
+        let first_li = styled.children.iter().find(|c| c.node.is_element()).unwrap();
+
+        assert!(first_li.value("color").is_some());
+    }
+
+    #[test]
+    fn test_attr_selector_existence_requires_attribute_present() {
+        let dom = Html::parse("<div><input type=\"text\"></input><input></input></div>".to_string());
+        let stylesheet = Css::parse("input[type] { color: #ff0000; }".to_string());
+
+        let styled = style_tree(&dom, &stylesheet);
+
+        assert!(styled.children[0].value("color").is_some());
+        assert!(styled.children[1].value("color").is_none());
+    }
+}